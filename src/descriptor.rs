@@ -0,0 +1,159 @@
+use std::f32::consts::PI;
+
+use crate::grayscale::Grayscale;
+use crate::keypoint::Keypoint;
+
+/// Bins of the histogram used to find each keypoint's dominant orientation.
+const ORIENTATION_BINS: usize = 36;
+/// Side length, in grid cells, of the descriptor's spatial layout.
+const GRID: usize = 4;
+/// Orientation bins accumulated per grid cell.
+const CELL_BINS: usize = 8;
+/// Side length, in samples, of the window the descriptor is built from.
+const WINDOW: i32 = 16;
+const CELL_SIZE: i32 = WINDOW / GRID as i32;
+const LEN: usize = GRID * GRID * CELL_BINS;
+
+pub struct Descriptor {
+	pub keypoint: Keypoint,
+	pub values: [f32; LEN],
+}
+
+// Central-difference gradient; zero outside the image so border samples
+// simply don't contribute to a histogram.
+fn gradient_at(image: &Grayscale, x: i32, y: i32) -> (f32, f32) {
+	if x <= 0 || y <= 0 || x >= image.width as i32 - 1 || y >= image.height as i32 - 1 {
+		return (0., 0.);
+	}
+
+	let dx = image.get((x + 1) as u32, y as u32) - image.get((x - 1) as u32, y as u32);
+	let dy = image.get(x as u32, (y + 1) as u32) - image.get(x as u32, (y - 1) as u32);
+	(dx, dy)
+}
+
+fn dominant_orientation(image: &Grayscale, cx: f32, cy: f32, sigma: f32) -> f32 {
+	let weight_sigma = 1.5 * sigma;
+	let radius = (3. * weight_sigma).round() as i32;
+	let mut histogram = [0f32; ORIENTATION_BINS];
+
+	for dy in -radius..=radius {
+		for dx in -radius..=radius {
+			let (gx, gy) = gradient_at(image, cx as i32 + dx, cy as i32 + dy);
+			let magnitude = (gx * gx + gy * gy).sqrt();
+			if magnitude == 0. {
+				continue;
+			}
+
+			let angle = gy.atan2(gx);
+			let weight = (-((dx * dx + dy * dy) as f32) / (2. * weight_sigma * weight_sigma)).exp();
+			let bin = (((angle + PI) / (2. * PI)) * ORIENTATION_BINS as f32) as usize % ORIENTATION_BINS;
+			histogram[bin] += magnitude * weight;
+		}
+	}
+
+	let peak = histogram.iter().enumerate()
+		.fold((0, histogram[0]), |best, (i, &v)| if v > best.1 { (i, v) } else { best })
+		.0;
+
+	(peak as f32 + 0.5) / ORIENTATION_BINS as f32 * 2. * PI - PI
+}
+
+fn normalize(values: &mut [f32; LEN]) {
+	let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+	if norm > 1e-6 {
+		for v in values.iter_mut() {
+			*v /= norm;
+		}
+	}
+}
+
+/// Builds the 128-dimensional SIFT descriptor for `keypoint` by sampling
+/// `image`, which must be the Gaussian level `keypoint` was detected at
+/// (i.e. `octaves[keypoint.octave].gaussians[keypoint.scale]`).
+pub fn compute(image: &Grayscale, keypoint: &Keypoint) -> Descriptor {
+	let orientation = dominant_orientation(image, keypoint.local_x, keypoint.local_y, keypoint.local_sigma);
+	let cos_t = orientation.cos();
+	let sin_t = orientation.sin();
+	let weight_sigma = WINDOW as f32 / 2.;
+
+	let mut histograms = [[0f32; CELL_BINS]; GRID * GRID];
+
+	for i in -(WINDOW / 2)..(WINDOW / 2) {
+		for j in -(WINDOW / 2)..(WINDOW / 2) {
+			// Sample offset rotated into the keypoint's dominant orientation.
+			let rx = i as f32 * cos_t - j as f32 * sin_t;
+			let ry = i as f32 * sin_t + j as f32 * cos_t;
+
+			let sample_x = (keypoint.local_x + rx).round() as i32;
+			let sample_y = (keypoint.local_y + ry).round() as i32;
+
+			let (gx, gy) = gradient_at(image, sample_x, sample_y);
+			let magnitude = (gx * gx + gy * gy).sqrt();
+			if magnitude == 0. {
+				continue;
+			}
+
+			let mut angle = gy.atan2(gx) - orientation;
+			while angle < 0. {
+				angle += 2. * PI;
+			}
+			while angle >= 2. * PI {
+				angle -= 2. * PI;
+			}
+
+			let weight = (-(rx * rx + ry * ry) / (2. * weight_sigma * weight_sigma)).exp();
+
+			let cell_col = ((i + WINDOW / 2) / CELL_SIZE).min(GRID as i32 - 1);
+			let cell_row = ((j + WINDOW / 2) / CELL_SIZE).min(GRID as i32 - 1);
+			let cell = (cell_row * GRID as i32 + cell_col) as usize;
+			let bin = ((angle / (2. * PI) * CELL_BINS as f32) as usize).min(CELL_BINS - 1);
+
+			histograms[cell][bin] += magnitude * weight;
+		}
+	}
+
+	let mut values = [0f32; LEN];
+	for (cell, histogram) in histograms.iter().enumerate() {
+		values[cell * CELL_BINS..(cell + 1) * CELL_BINS].copy_from_slice(histogram);
+	}
+
+	normalize(&mut values);
+	for v in values.iter_mut() {
+		*v = v.min(0.2);
+	}
+	normalize(&mut values);
+
+	Descriptor { keypoint: *keypoint, values }
+}
+
+fn distance(a: &[f32; LEN], b: &[f32; LEN]) -> f32 {
+	a.iter().zip(b.iter()).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt()
+}
+
+/// Matches descriptors of `a` against `b` using Lowe's ratio test, returning
+/// `(index in a, index in b)` pairs.
+pub fn match_descriptors(a: &[Descriptor], b: &[Descriptor]) -> Vec<(usize, usize)> {
+	const RATIO: f32 = 0.8;
+	let mut matches = Vec::new();
+
+	for (i, da) in a.iter().enumerate() {
+		let mut best = (usize::MAX, f32::INFINITY);
+		let mut second = (usize::MAX, f32::INFINITY);
+
+		for (j, db) in b.iter().enumerate() {
+			let d = distance(&da.values, &db.values);
+			if d < best.1 {
+				second = best;
+				best = (j, d);
+			} else if d < second.1 {
+				second = (j, d);
+			}
+		}
+
+		if second.1.is_finite() && best.1 / second.1 < RATIO {
+			matches.push((i, best.0));
+		}
+	}
+
+	matches
+}
@@ -0,0 +1,50 @@
+/// Number of box-blur passes used to approximate a single Gaussian blur.
+pub(crate) const NUM_PASSES: u32 = 3;
+
+#[inline(always)]
+pub(crate) fn clamp_i32(n: i32, min: i32, max: i32) -> i32 {
+	if n < min {
+		min
+	} else if n > max {
+		max
+	} else {
+		n
+	}
+}
+
+// Kovesi's "fast almost-Gaussian" box radii: `n` box blurs whose combined
+// variance approximates a Gaussian of standard deviation `sigma`.
+pub(crate) fn box_radii(sigma: f32, n: u32) -> Vec<i32> {
+	let w_ideal = (12. * sigma * sigma / n as f32 + 1.).sqrt();
+	let mut wl = w_ideal.floor() as i32;
+	if wl % 2 == 0 {
+		wl -= 1;
+	}
+	let wu = wl + 2;
+
+	let m = ((12. * sigma * sigma - (n * (wl * wl) as u32) as f32
+		- (4 * n) as f32 * wl as f32 - (3 * n) as f32)
+		/ (-4. * wl as f32 - 4.)).round() as i32;
+
+	(0..n as i32)
+		.map(|i| if i < m { (wl - 1) / 2 } else { (wu - 1) / 2 })
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn box_radii_sigma0() {
+		// SIGMA0 = 1.6, the base blur used to seed an octave: all three
+		// passes land on the same radius.
+		assert_eq!(box_radii(1.6, 3), vec![1, 1, 1]);
+	}
+
+	#[test]
+	fn box_radii_small_sigma() {
+		// A narrower sigma splits across two box widths (0, 0, 1).
+		assert_eq!(box_radii(1.0, 3), vec![0, 0, 1]);
+	}
+}
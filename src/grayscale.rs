@@ -0,0 +1,148 @@
+use std::cmp::min;
+
+use image::DynamicImage;
+use image::GenericImageView;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::gaussian;
+
+/// A single-channel, linear-light intensity image used by the scale space.
+/// SIFT operates on scalar intensity, so color is dropped here and only
+/// restored for the final visualization.
+pub struct Grayscale {
+	pub width: u32,
+	pub height: u32,
+	data: Vec<f32>,
+}
+
+impl Grayscale {
+	pub fn get(&self, x: u32, y: u32) -> f32 {
+		self.data[(y * self.width + x) as usize]
+	}
+}
+
+fn linearize(c: f32) -> f32 {
+	if c <= 0.04045 {
+		c / 12.92
+	} else {
+		((c + 0.055) / 1.055).powf(2.4)
+	}
+}
+
+pub fn from_image(img: &DynamicImage) -> Grayscale {
+	let (width, height) = (img.width(), img.height());
+	let mut data = Vec::with_capacity((width * height) as usize);
+
+	for y in 0..height {
+		for x in 0..width {
+			let color = img.get_pixel(x, y);
+			let r = linearize(color[0] as f32 / 255.);
+			let g = linearize(color[1] as f32 / 255.);
+			let b = linearize(color[2] as f32 / 255.);
+			data.push(0.2126 * r + 0.7152 * g + 0.0722 * b);
+		}
+	}
+
+	Grayscale { width, height, data }
+}
+
+fn box_blur_row(width: u32, radius: i32, window: f32, row: &[f32], row_dst: &mut [f32]) {
+	let mut sum = 0f32;
+	for i in -radius..=radius {
+		let xi = gaussian::clamp_i32(i, 0, width as i32 - 1) as usize;
+		sum += row[xi];
+	}
+
+	for (x, dst) in row_dst.iter_mut().enumerate() {
+		*dst = sum / window;
+
+		let leaving = gaussian::clamp_i32(x as i32 - radius, 0, width as i32 - 1) as usize;
+		let entering = gaussian::clamp_i32(x as i32 + radius + 1, 0, width as i32 - 1) as usize;
+		sum += row[entering] - row[leaving];
+	}
+}
+
+// Running-sum box blur over a single channel, O(width * height) per pass.
+// Rows are independent, so with the `parallel` feature each row is written
+// through its own disjoint mutable slice of the output buffer.
+#[cfg(feature = "parallel")]
+fn box_blur_horizontal(src: &[f32], width: u32, height: u32, radius: i32) -> Vec<f32> {
+	debug_assert_eq!(src.len(), (width * height) as usize);
+	let window = (2 * radius + 1) as f32;
+	let mut dst = vec![0f32; src.len()];
+
+	dst.par_chunks_mut(width as usize).enumerate().for_each(|(y, row_dst)| {
+		let row = &src[y * width as usize..(y + 1) * width as usize];
+		box_blur_row(width, radius, window, row, row_dst);
+	});
+
+	dst
+}
+
+#[cfg(not(feature = "parallel"))]
+fn box_blur_horizontal(src: &[f32], width: u32, height: u32, radius: i32) -> Vec<f32> {
+	let window = (2 * radius + 1) as f32;
+	let mut dst = vec![0f32; src.len()];
+
+	for y in 0..height as usize {
+		let row = &src[y * width as usize..(y + 1) * width as usize];
+		let row_dst = &mut dst[y * width as usize..(y + 1) * width as usize];
+		box_blur_row(width, radius, window, row, row_dst);
+	}
+
+	dst
+}
+
+fn transpose(src: &[f32], width: u32, height: u32) -> Vec<f32> {
+	let mut dst = vec![0f32; src.len()];
+	for y in 0..height {
+		for x in 0..width {
+			dst[(x * height + y) as usize] = src[(y * width + x) as usize];
+		}
+	}
+	dst
+}
+
+// The vertical pass is the horizontal pass on the transposed buffer, which
+// lets it share the same row-parallel implementation above.
+fn box_blur_vertical(src: &[f32], width: u32, height: u32, radius: i32) -> Vec<f32> {
+	let transposed = transpose(src, width, height);
+	let blurred = box_blur_horizontal(&transposed, height, width, radius);
+	transpose(&blurred, height, width)
+}
+
+pub fn blur(img: &Grayscale, sigma: f32) -> Grayscale {
+	let (width, height) = (img.width, img.height);
+	let mut data = img.data.clone();
+
+	for radius in gaussian::box_radii(sigma, gaussian::NUM_PASSES) {
+		data = box_blur_vertical(&box_blur_horizontal(&data, width, height, radius), width, height, radius);
+	}
+
+	Grayscale { width, height, data }
+}
+
+// Halves resolution with a 2x2 box average, the scalar-buffer equivalent of
+// the `imageops` downsampling used between octaves.
+pub fn downsample_half(img: &Grayscale) -> Grayscale {
+	// Round up rather than truncate: on an odd dimension, truncating would
+	// drop the trailing row/column outright instead of folding it into a
+	// (clamped) last 2x2 block, and the loss would compound over
+	// `NUM_OCTAVES` successive halvings.
+	let new_width = std::cmp::max(1, img.width.div_ceil(2));
+	let new_height = std::cmp::max(1, img.height.div_ceil(2));
+	let mut data = Vec::with_capacity((new_width * new_height) as usize);
+
+	for oy in 0..new_height {
+		for ox in 0..new_width {
+			let x0 = ox * 2;
+			let y0 = oy * 2;
+			let x1 = min(x0 + 1, img.width - 1);
+			let y1 = min(y0 + 1, img.height - 1);
+			data.push((img.get(x0, y0) + img.get(x1, y0) + img.get(x0, y1) + img.get(x1, y1)) / 4.);
+		}
+	}
+
+	Grayscale { width: new_width, height: new_height, data }
+}
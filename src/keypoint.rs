@@ -0,0 +1,279 @@
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::scale_space::{DogImage, Octave, ScaleSpace};
+
+/// Minimum |interpolated DoG value| for a candidate to be kept; rejects
+/// low-contrast responses.
+const CONTRAST_THRESHOLD: f32 = 0.03;
+/// Edge response ratio threshold, as used in the original SIFT paper.
+const EDGE_RATIO: f32 = 10.;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Keypoint {
+	/// Position in the original (octave 0) image, used for drawing and
+	/// matching across images.
+	pub x: f32,
+	pub y: f32,
+	pub octave: usize,
+	pub scale: usize,
+	/// Scale of the blur at which this keypoint was detected, in original
+	/// image units.
+	pub sigma: f32,
+	/// Position within the octave's own (possibly downsampled) images, used
+	/// to sample the descriptor from `Octave::gaussians`.
+	pub local_x: f32,
+	pub local_y: f32,
+	pub local_sigma: f32,
+}
+
+fn is_extremum(dog: &[DogImage], s: usize, x: u32, y: u32) -> bool {
+	let value = dog[s].get(x, y);
+	let mut is_max = true;
+	let mut is_min = true;
+
+	for ds in 0..3 {
+		let layer = &dog[s - 1 + ds];
+		for dx in -1i32..=1 {
+			for dy in -1i32..=1 {
+				if ds == 1 && dx == 0 && dy == 0 {
+					continue;
+				}
+
+				let neighbor = layer.get((x as i32 + dx) as u32, (y as i32 + dy) as u32);
+				if neighbor >= value {
+					is_max = false;
+				}
+				if neighbor <= value {
+					is_min = false;
+				}
+			}
+		}
+	}
+
+	is_max || is_min
+}
+
+// Gradient and Hessian of the DoG function at (x, y, s), by central finite
+// differences over the three adjacent DoG layers.
+fn gradient(dog: &[DogImage], s: usize, x: u32, y: u32) -> [f32; 3] {
+	let dx = (dog[s].get(x + 1, y) - dog[s].get(x - 1, y)) / 2.;
+	let dy = (dog[s].get(x, y + 1) - dog[s].get(x, y - 1)) / 2.;
+	let ds = (dog[s + 1].get(x, y) - dog[s - 1].get(x, y)) / 2.;
+	[dx, dy, ds]
+}
+
+fn hessian(dog: &[DogImage], s: usize, x: u32, y: u32) -> [[f32; 3]; 3] {
+	let center = dog[s].get(x, y);
+
+	let dxx = dog[s].get(x + 1, y) + dog[s].get(x - 1, y) - 2. * center;
+	let dyy = dog[s].get(x, y + 1) + dog[s].get(x, y - 1) - 2. * center;
+	let dss = dog[s + 1].get(x, y) + dog[s - 1].get(x, y) - 2. * center;
+
+	let dxy = (dog[s].get(x + 1, y + 1) - dog[s].get(x + 1, y - 1)
+		- dog[s].get(x - 1, y + 1) + dog[s].get(x - 1, y - 1)) / 4.;
+	let dxs = (dog[s + 1].get(x + 1, y) - dog[s + 1].get(x - 1, y)
+		- dog[s - 1].get(x + 1, y) + dog[s - 1].get(x - 1, y)) / 4.;
+	let dys = (dog[s + 1].get(x, y + 1) - dog[s + 1].get(x, y - 1)
+		- dog[s - 1].get(x, y + 1) + dog[s - 1].get(x, y - 1)) / 4.;
+
+	[
+		[dxx, dxy, dxs],
+		[dxy, dyy, dys],
+		[dxs, dys, dss],
+	]
+}
+
+// Solves `h * offset = -g` for a symmetric 3x3 system using Cramer's rule.
+fn solve3x3(h: &[[f32; 3]; 3], g: &[f32; 3]) -> Option<[f32; 3]> {
+	let det = h[0][0] * (h[1][1] * h[2][2] - h[1][2] * h[2][1])
+		- h[0][1] * (h[1][0] * h[2][2] - h[1][2] * h[2][0])
+		+ h[0][2] * (h[1][0] * h[2][1] - h[1][1] * h[2][0]);
+
+	if det.abs() < 1e-12 {
+		return None;
+	}
+
+	let mut offset = [0f32; 3];
+	for col in 0..3 {
+		let mut m = *h;
+		for row in 0..3 {
+			m[row][col] = -g[row];
+		}
+		let det_col = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+			- m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+			+ m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+		offset[col] = det_col / det;
+	}
+
+	Some(offset)
+}
+
+fn passes_edge_test(h: &[[f32; 3]; 3]) -> bool {
+	let dxx = h[0][0];
+	let dyy = h[1][1];
+	let dxy = h[0][1];
+
+	let trace = dxx + dyy;
+	let det = dxx * dyy - dxy * dxy;
+	if det <= 0. {
+		return false;
+	}
+
+	(trace * trace) / det < (EDGE_RATIO + 1.) * (EDGE_RATIO + 1.) / EDGE_RATIO
+}
+
+/// Number of times the quadratic fit may re-center on a neighboring sample
+/// before giving up, as in Lowe's original algorithm.
+const MAX_INTERPOLATION_STEPS: u32 = 5;
+
+// Result of a converged `refine` call: the (possibly re-centered) integer
+// sample, the sub-pixel offset from it, and the gradient/Hessian it was
+// computed from, so callers don't redo that work for the contrast/edge
+// tests.
+struct Refined {
+	s: usize,
+	x: u32,
+	y: u32,
+	offset: [f32; 3],
+	gradient: [f32; 3],
+	hessian: [[f32; 3]; 3],
+}
+
+// Iteratively re-centers the quadratic (Taylor) fit on the integer sample
+// nearest the current offset until `offset` settles within half a pixel of
+// the sample it was computed at, so the fit stays anchored to the extremum
+// it started from instead of drifting arbitrarily far from it. Gives up if
+// it walks off the usable region of the DoG stack.
+fn refine(dog: &[DogImage], mut s: usize, mut x: u32, mut y: u32) -> Option<Refined> {
+	for _ in 0..MAX_INTERPOLATION_STEPS {
+		let g = gradient(dog, s, x, y);
+		let h = hessian(dog, s, x, y);
+		let offset = solve3x3(&h, &g)?;
+
+		if offset.iter().all(|o| o.abs() <= 0.5) {
+			return Some(Refined { s, x, y, offset, gradient: g, hessian: h });
+		}
+
+		let width = dog[s].width;
+		let height = dog[s].height;
+		let new_x = x as i32 + offset[0].round() as i32;
+		let new_y = y as i32 + offset[1].round() as i32;
+		let new_s = s as i32 + offset[2].round() as i32;
+
+		if new_x < 1 || new_x >= width as i32 - 1
+			|| new_y < 1 || new_y >= height as i32 - 1
+			|| new_s < 1 || new_s >= dog.len() as i32 - 1 {
+			return None;
+		}
+
+		x = new_x as u32;
+		y = new_y as u32;
+		s = new_s as usize;
+	}
+
+	None
+}
+
+// Candidate extrema, refinement and edge rejection for a single row of a
+// single DoG layer. Rows are independent, so this is the unit of work the
+// `parallel` feature fans out over.
+fn detect_row(octave: &Octave, octave_idx: usize, s: usize, y: u32) -> Vec<Keypoint> {
+	let width = octave.dog[s].width;
+	let mut keypoints = Vec::new();
+
+	for x in 1..width - 1 {
+		if !is_extremum(&octave.dog, s, x, y) {
+			continue;
+		}
+
+		let Refined { s, x, y, offset, gradient: g, hessian: h } = match refine(&octave.dog, s, x, y) {
+			Some(refined) => refined,
+			None => continue,
+		};
+
+		let value = octave.dog[s].get(x, y)
+			+ 0.5 * (g[0] * offset[0] + g[1] * offset[1] + g[2] * offset[2]);
+		if value.abs() < CONTRAST_THRESHOLD {
+			continue;
+		}
+
+		if !passes_edge_test(&h) {
+			continue;
+		}
+
+		let scale_factor = 2f32.powi(octave_idx as i32);
+		let local_x = x as f32 + offset[0];
+		let local_y = y as f32 + offset[1];
+		keypoints.push(Keypoint {
+			x: local_x * scale_factor,
+			y: local_y * scale_factor,
+			octave: octave_idx,
+			scale: s,
+			sigma: octave.sigmas[s] * scale_factor,
+			local_x,
+			local_y,
+			local_sigma: octave.sigmas[s],
+		});
+	}
+
+	keypoints
+}
+
+#[cfg(feature = "parallel")]
+fn detect_layer(octave: &Octave, octave_idx: usize, s: usize) -> Vec<Keypoint> {
+	let height = octave.dog[s].height;
+	(1..height - 1)
+		.into_par_iter()
+		.flat_map(|y| detect_row(octave, octave_idx, s, y))
+		.collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn detect_layer(octave: &Octave, octave_idx: usize, s: usize) -> Vec<Keypoint> {
+	let height = octave.dog[s].height;
+	(1..height - 1)
+		.flat_map(|y| detect_row(octave, octave_idx, s, y))
+		.collect()
+}
+
+pub fn detect(scale_space: &ScaleSpace) -> Vec<Keypoint> {
+	let mut keypoints = Vec::new();
+
+	for (octave_idx, octave) in scale_space.octaves.iter().enumerate() {
+		for s in 1..octave.dog.len() - 1 {
+			keypoints.extend(detect_layer(octave, octave_idx, s));
+		}
+	}
+
+	keypoints
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn solve3x3_diagonal() {
+		let h = [[2., 0., 0.], [0., 3., 0.], [0., 0., 4.]];
+		let g = [4., 9., 8.];
+		let offset = solve3x3(&h, &g).unwrap();
+		assert_eq!(offset, [-2., -3., -2.]);
+	}
+
+	#[test]
+	fn solve3x3_coupled() {
+		// Hand-solved: h * x = -g with x0 pinned by the third row, then back
+		// substitution through the first two.
+		let h = [[2., 1., 1.], [1., 3., 2.], [1., 0., 0.]];
+		let g = [4., 5., 6.];
+		let offset = solve3x3(&h, &g).unwrap();
+		assert_eq!(offset, [-6., -15., 23.]);
+	}
+
+	#[test]
+	fn solve3x3_singular() {
+		let h = [[1., 2., 3.], [2., 4., 6.], [1., 0., 0.]];
+		assert!(solve3x3(&h, &[1., 1., 1.]).is_none());
+	}
+}
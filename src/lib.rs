@@ -0,0 +1,138 @@
+use std::cmp::max;
+
+use image::DynamicImage;
+use image::GenericImage;
+use image::Rgba;
+use image::imageops;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub mod descriptor;
+pub mod gaussian;
+pub mod grayscale;
+pub mod keypoint;
+pub mod scale_space;
+pub mod source;
+
+pub use source::{FileSource, ImageSource};
+
+fn draw_point(img: &mut DynamicImage, x: usize, y: usize, radius: isize) {
+	for i in -radius..radius {
+		for j in -radius..radius {
+			if i * i + j * j > radius * radius {
+				continue;
+			}
+
+			let x_coord = x as isize + i;
+			let y_coord = y as isize + j;
+			if x_coord < 0 || x_coord >= img.width() as isize
+				|| y_coord < 0 || y_coord >= img.height() as isize {
+				continue;
+			}
+			img.put_pixel(x_coord as _, y_coord as _, Rgba([255, 0, 255, 255]));
+		}
+	}
+}
+
+// Bresenham's line algorithm, used to draw correspondences between matched
+// keypoints across the stacked image.
+fn draw_line(img: &mut DynamicImage, (mut x0, mut y0): (isize, isize), (x1, y1): (isize, isize)) {
+	let dx = (x1 - x0).abs();
+	let sx = if x0 < x1 { 1 } else { -1 };
+	let dy = -(y1 - y0).abs();
+	let sy = if y0 < y1 { 1 } else { -1 };
+	let mut err = dx + dy;
+
+	loop {
+		if x0 >= 0 && y0 >= 0 && x0 < img.width() as isize && y0 < img.height() as isize {
+			img.put_pixel(x0 as _, y0 as _, Rgba([255, 0, 255, 255]));
+		}
+		if x0 == x1 && y0 == y1 {
+			break;
+		}
+
+		let e2 = 2 * err;
+		if e2 >= dy {
+			err += dy;
+			x0 += sx;
+		}
+		if e2 <= dx {
+			err += dx;
+			y0 += sy;
+		}
+	}
+}
+
+/// Runs the full DoG/SIFT pipeline over `image_sources`, stacking the
+/// resulting images vertically and writing `output.jpg` with detected
+/// keypoints and cross-image correspondences drawn on top.
+pub fn run(image_sources: Vec<Box<dyn ImageSource>>) {
+	let mut sources = Vec::<DynamicImage>::new();
+	for (i, image_source) in image_sources.iter().enumerate() {
+		let img_result = image_source.content();
+		if img_result.is_err() {
+			eprintln!("Failed to open image #{}!", i);
+			std::process::exit(1);
+		}
+
+		//let img_pre = img.resize(400, 300, imageops::FilterType::Triangle); // TODO Remove
+		sources.push(img_result.unwrap());
+	}
+
+	fn descriptors_for(img: &DynamicImage) -> Vec<descriptor::Descriptor> {
+		let scale_space = scale_space::build(img);
+		keypoint::detect(&scale_space).iter()
+			.map(|kp| descriptor::compute(&scale_space.octaves[kp.octave].gaussians[kp.scale], kp))
+			.collect()
+	}
+
+	#[cfg(feature = "parallel")]
+	let descriptors_per_image: Vec<Vec<descriptor::Descriptor>> = sources.par_iter()
+		.map(descriptors_for)
+		.collect();
+	#[cfg(not(feature = "parallel"))]
+	let descriptors_per_image: Vec<Vec<descriptor::Descriptor>> = sources.iter()
+		.map(descriptors_for)
+		.collect();
+
+	let mut images = Vec::<(DynamicImage, usize, Vec<descriptor::Descriptor>)>::new();
+	let mut width: usize = 0;
+	let mut height: usize = 0;
+	for (img, descriptors) in sources.into_iter().zip(descriptors_per_image) {
+		let y = height;
+		width = max(width, img.width() as usize);
+		height += img.height() as usize;
+		images.push((img, y, descriptors));
+	}
+
+	let mut final_image = DynamicImage::new_rgb8(width as _, height as _);
+	for (img, y, _) in images.iter() {
+		imageops::overlay(&mut final_image, img, 0, *y as _);
+	}
+
+	for (_, y, descriptors) in images.iter() {
+		for d in descriptors {
+			draw_point(&mut final_image, d.keypoint.x as usize, d.keypoint.y as usize + y, 3);
+		}
+	}
+
+	for pair in images.windows(2) {
+		let (_, y_a, descriptors_a) = &pair[0];
+		let (_, y_b, descriptors_b) = &pair[1];
+
+		for (ia, ib) in descriptor::match_descriptors(descriptors_a, descriptors_b) {
+			let a = &descriptors_a[ia].keypoint;
+			let b = &descriptors_b[ib].keypoint;
+			draw_line(
+				&mut final_image,
+				(a.x as isize, a.y as isize + *y_a as isize),
+				(b.x as isize, b.y as isize + *y_b as isize),
+			);
+		}
+	}
+
+    if final_image.save("output.jpg").is_err() {
+		eprintln!("Failed to save image!");
+		std::process::exit(1);
+	}
+}
@@ -0,0 +1,77 @@
+use image::DynamicImage;
+
+use crate::grayscale::{self, Grayscale};
+
+/// Number of octaves built from the input image (each halving resolution).
+pub const NUM_OCTAVES: u32 = 4;
+/// Number of scale intervals per octave; each octave holds `S + 3` blurred
+/// images and `S + 2` DoG images.
+pub const S: u32 = 3;
+/// Base Gaussian standard deviation of the first level of the first octave.
+pub const SIGMA0: f32 = 1.6;
+
+// A floating point difference-of-Gaussian image. Kept distinct from
+// `Grayscale` so the DoG stack reads as its own stage of the pipeline.
+pub struct DogImage {
+	pub width: u32,
+	pub height: u32,
+	data: Vec<f32>,
+}
+
+impl DogImage {
+	pub fn get(&self, x: u32, y: u32) -> f32 {
+		self.data[(y * self.width + x) as usize]
+	}
+}
+
+pub struct Octave {
+	pub gaussians: Vec<Grayscale>,
+	pub dog: Vec<DogImage>,
+	pub sigmas: Vec<f32>,
+}
+
+pub struct ScaleSpace {
+	pub octaves: Vec<Octave>,
+}
+
+fn build_octave(base: &Grayscale, sigma0: f32) -> Octave {
+	let k = 2f32.powf(1. / S as f32);
+
+	let mut gaussians = Vec::with_capacity((S + 3) as usize);
+	let mut sigmas = Vec::with_capacity((S + 3) as usize);
+	for i in 0..(S + 3) {
+		let sigma = sigma0 * k.powi(i as i32);
+		gaussians.push(grayscale::blur(base, sigma));
+		sigmas.push(sigma);
+	}
+
+	let width = base.width;
+	let height = base.height;
+	let mut dog = Vec::with_capacity((S + 2) as usize);
+	for i in 0..(S + 2) as usize {
+		let mut data = Vec::with_capacity((width * height) as usize);
+		for y in 0..height {
+			for x in 0..width {
+				data.push(gaussians[i + 1].get(x, y) - gaussians[i].get(x, y));
+			}
+		}
+		dog.push(DogImage { width, height, data });
+	}
+
+	Octave { gaussians, dog, sigmas }
+}
+
+pub fn build(img: &DynamicImage) -> ScaleSpace {
+	let mut octaves = Vec::with_capacity(NUM_OCTAVES as usize);
+
+	let mut base = grayscale::from_image(img);
+	for i in 0..NUM_OCTAVES {
+		octaves.push(build_octave(&base, SIGMA0));
+
+		if i + 1 < NUM_OCTAVES {
+			base = grayscale::downsample_half(&base);
+		}
+	}
+
+	ScaleSpace { octaves }
+}
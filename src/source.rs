@@ -0,0 +1,26 @@
+use image::DynamicImage;
+use image::error::ImageResult;
+use image::io::Reader as ImageReader;
+
+/// A single frame fed into the SIFT pipeline. Lets callers provide images
+/// from origins other than the filesystem (in-memory buffers, screen
+/// captures, camera frames) without touching the core pipeline.
+pub trait ImageSource {
+	fn content(&self) -> ImageResult<DynamicImage>;
+}
+
+pub struct FileSource {
+	path: String,
+}
+
+impl FileSource {
+	pub fn new(path: String) -> Self {
+		Self { path }
+	}
+}
+
+impl ImageSource for FileSource {
+	fn content(&self) -> ImageResult<DynamicImage> {
+		ImageReader::open(&self.path)?.decode()
+	}
+}